@@ -0,0 +1,6 @@
+-- Baseline for databases created before migrations were tracked.
+CREATE TABLE IF NOT EXISTS links (
+    id UUID PRIMARY KEY,
+    original_url TEXT NOT NULL,
+    short_code TEXT NOT NULL UNIQUE
+);
@@ -0,0 +1,5 @@
+ALTER TABLE links ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS links_expires_at_idx
+    ON links (expires_at)
+    WHERE expires_at IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Rows that predate this column get the migration time.
+ALTER TABLE links ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
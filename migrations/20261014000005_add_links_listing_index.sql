@@ -0,0 +1,2 @@
+-- Keyset pagination for GET /api/links walks (created_at, id) newest first.
+CREATE INDEX IF NOT EXISTS links_created_at_id_idx ON links (created_at DESC, id DESC);
@@ -1,5 +1,28 @@
+use std::{net::IpAddr, sync::Arc};
 use sqlx::PgPool;
+use crate::middleware::rate_limit::RateLimiter;
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
-}
\ No newline at end of file
+    pub origin: Arc<PublicOrigin>,
+    pub admin_token: Option<Arc<str>>,
+    /// PREVIEW_IMAGE_URL: the og:image every preview card uses, if set.
+    pub preview_image: Option<Arc<str>>,
+    /// Peers allowed to tell us the client address via X-Forwarded-For.
+    pub trusted_proxies: Arc<[IpAddr]>,
+    pub shorten_limiter: Arc<RateLimiter>,
+    pub check_limiter: Arc<RateLimiter>,
+}
+
+/// Where short links are served from, fixed at startup.
+pub struct PublicOrigin {
+    /// Absolute origin short URLs are built on, without a trailing slash.
+    pub base_url: String,
+    /// Host of `base_url`, lowercase and without a trailing dot.
+    pub host: String,
+    /// Non-empty path segments of `base_url`, e.g. `["s"]` for `https://x.com/s`.
+    pub path_segments: Vec<String>,
+    /// ALLOW_CHAIN_REDIRECTS=true: let links point at other short links.
+    pub allow_chain_redirects: bool,
+}
@@ -7,4 +7,11 @@ pub async fn connect_db(database_url: &str) -> PgPool {
         .connect(database_url)
         .await
         .expect("Failed to connect to database")
-}
\ No newline at end of file
+}
+
+pub async fn run_migrations(pool: &PgPool) {
+    sqlx::migrate!()
+        .run(pool)
+        .await
+        .expect("Failed to run database migrations")
+}
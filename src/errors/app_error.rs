@@ -1,44 +1,83 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use thiserror::Error;
-use crate::models::response::ApiResponse;
+use crate::models::response::{ApiErrorResponse, ApiResponse};
 
 #[derive(Error, Debug)]
 pub enum AppError {
-    #[error("invalid input")]
-    Validation,
+    #[error("invalid input: {0}")]
+    Validation(String),
+    #[error("unauthorized")]
+    Unauthorized,
     #[error("not found")]
     NotFound,
-    #[allow(dead_code)]
+    #[error("gone")]
+    Gone,
     #[error("conflict")]
     Conflict,
+    #[error("rate limited, retry after {0}s")]
+    RateLimited(u64),
     #[error("database error")]
     Database(#[from] sqlx::Error),
     #[allow(dead_code)]
     #[error("internal error")]
     Internal,
 }
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, code, message) = match self {
-            AppError::Validation =>
-                (StatusCode::BAD_REQUEST, "VALIDATION", "invalid input"),
+impl AppError {
+    fn parts(&self) -> (StatusCode, &'static str, String) {
+        match self {
+            AppError::Validation(message) =>
+                (StatusCode::BAD_REQUEST, "VALIDATION", message.clone()),
+            AppError::Unauthorized =>
+                (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", "missing or invalid token".into()),
             AppError::NotFound =>
-                (StatusCode::NOT_FOUND, "NOT_FOUND", "not found"),
+                (StatusCode::NOT_FOUND, "NOT_FOUND", "not found".into()),
+            AppError::Gone =>
+                (StatusCode::GONE, "GONE", "link has expired".into()),
             AppError::Conflict =>
-                (StatusCode::CONFLICT, "CONFLICT", "already exists"),
+                (StatusCode::CONFLICT, "CONFLICT", "already exists".into()),
+            AppError::RateLimited(_) =>
+                (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", "too many requests".into()),
             AppError::Database(_) =>
-                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", "internal error"),
+                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", "internal error".into()),
             AppError::Internal =>
-                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", "internal error"),
-        };
+                (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL", "internal error".into()),
+        }
+    }
+
+    /// The `code`/`message` pair clients see, for errors reported inline
+    /// rather than as the whole response.
+    pub fn detail(&self) -> ApiErrorResponse {
+        let (_, code, message) = self.parts();
+        ApiErrorResponse {
+            code: code.into(),
+            message,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, code, message) = self.parts();
         let body = ApiResponse::<()>::error(
             code.into(),
-            message.into(),
+            message,
         );
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        match self {
+            AppError::RateLimited(secs) => {
+                response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            }
+            AppError::Unauthorized => {
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            _ => {}
+        }
+        response
     }
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use crate::{app_state::AppState, errors::app_error::AppError};
+
+/// Guards admin routes: requires `Authorization: Bearer <ADMIN_TOKEN>`.
+/// With no ADMIN_TOKEN configured every request is rejected.
+pub struct AdminToken;
+
+impl FromRequestParts<AppState> for AdminToken {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let expected = state
+            .admin_token
+            .as_deref()
+            .ok_or(AppError::Unauthorized)?;
+
+        let provided = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split_once(' '))
+            // Auth schemes are case-insensitive (RFC 9110 §11.1).
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim_start())
+            .ok_or(AppError::Unauthorized)?;
+
+        if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            return Err(AppError::Unauthorized);
+        }
+
+        Ok(AdminToken)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
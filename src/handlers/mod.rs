@@ -1 +1,3 @@
-pub mod shortener_handler;
\ No newline at end of file
+pub mod preview_handler;
+pub mod shortener_handler;
+pub mod utm_handler;
\ No newline at end of file
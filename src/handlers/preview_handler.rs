@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use url::Url;
+use crate::{
+    app_state::AppState,
+    models::{
+        entity::ShortUrl,
+        response::{ApiResponse, LinkPreviewResponse},
+    },
+    services::shortener_service::ShortenerService,
+    errors::app_error::AppError,
+};
+
+/// Lets people see where a short link goes before following it. Clients
+/// asking for JSON (chat apps, browser extensions) get the link details;
+/// everyone else gets the Open Graph card.
+pub async fn preview(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let link = ShortenerService::find(&state.db, &code).await?;
+
+    if wants_json(&headers) {
+        let body = LinkPreviewResponse {
+            short_url: format!("{}/{}", state.origin.base_url, link.short_code),
+            short_code: link.short_code,
+            original_url: link.original_url,
+            created_at: link.created_at,
+            expires_at: link.expires_at,
+        };
+        return Ok(Json(ApiResponse::success(body)).into_response());
+    }
+
+    Ok(preview_card(&link, state.preview_image.as_deref())?.into_response())
+}
+
+fn wants_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json") && !accept.contains("text/html"))
+}
+
+/// Serves Open Graph tags for social scrapers and sends browsers straight
+/// on to the destination with a zero-second meta refresh.
+fn preview_card(link: &ShortUrl, image: Option<&str>) -> Result<Html<String>, AppError> {
+    // Never emit a refresh to javascript:, data: and the like.
+    let destination = Url::parse(&link.original_url)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or(AppError::NotFound)?;
+
+    let title = destination
+        .host_str()
+        .map(str::to_owned)
+        .unwrap_or_else(|| link.original_url.clone());
+
+    let url = escape_html(&link.original_url);
+    let title = escape_html(&title);
+    // We don't fetch the destination, so there is no per-link image; og:image
+    // is only emitted when PREVIEW_IMAGE_URL configures a shared one.
+    let image = image
+        .map(|src| format!("\n<meta property=\"og:image\" content=\"{}\">", escape_html(src)))
+        .unwrap_or_default();
+
+    Ok(Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<meta property="og:type" content="website">
+<meta property="og:title" content="{title}">
+<meta property="og:description" content="{url}">
+<meta property="og:url" content="{url}">{image}
+<meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+<p>Redirecting to <a href="{url}">{url}</a></p>
+</body>
+</html>
+"#
+    )))
+}
+
+fn escape_html(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -1,18 +1,81 @@
-use axum::{extract::State, Json};
-use sqlx::PgPool;
+use std::net::SocketAddr;
+use axum::{extract::{ConnectInfo, Path, Query, State}, http::HeaderMap, Json};
 use crate::{
+    app_state::AppState,
+    extractors::admin_token::AdminToken,
+    middleware::rate_limit::{client_ip, retry_after_secs},
     models::{
-        requests::CreateShortUrlRequest,
-        response::{ApiResponse, CreateShortUrlResponse},
+        requests::{
+            CreateBulkRequest, CreateShortUrlRequest, ListLinksQuery, PatchLinkRequest,
+            ShortCodeCheckQuery,
+        },
+        response::{
+            ApiResponse, BulkCreateResponse, CreateShortUrlResponse, LinkResponse, LinksPage,
+            ShortCodeAvailabilityResponse,
+        },
     },
     services::shortener_service::ShortenerService,
     errors::app_error::AppError,
 };
 
 pub async fn create_short_url(
-    State(pool): State<PgPool>,
+    State(state): State<AppState>,
     Json(payload): Json<CreateShortUrlRequest>,
 ) -> Result<Json<ApiResponse<CreateShortUrlResponse>>, AppError> {
-    let result = ShortenerService::create(&pool, payload).await?;
+    let result = ShortenerService::create(&state.db, &state.origin, payload).await?;
     Ok(Json(ApiResponse::success(result))) 
+}
+
+/// Rate limited here rather than in the router: each URL in the batch
+/// costs one hit against `shorten_limiter`, capped at its whole quota.
+pub async fn create_short_urls_bulk(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateBulkRequest>,
+) -> Result<Json<ApiResponse<Vec<BulkCreateResponse>>>, AppError> {
+    let cost = payload.urls.len().max(1);
+    state
+        .shorten_limiter
+        .check_n(client_ip(&headers, peer, &state.trusted_proxies), cost)
+        .map_err(|wait| AppError::RateLimited(retry_after_secs(wait)))?;
+
+    let result = ShortenerService::create_bulk(&state.db, &state.origin, payload).await?;
+    Ok(Json(ApiResponse::success(result)))
+}
+
+pub async fn check_short_code(
+    State(state): State<AppState>,
+    Query(query): Query<ShortCodeCheckQuery>,
+) -> Result<Json<ApiResponse<ShortCodeAvailabilityResponse>>, AppError> {
+    let result = ShortenerService::check_availability(&state.db, &query.code).await?;
+    Ok(Json(ApiResponse::success(result)))
+}
+
+pub async fn list_links(
+    _admin: AdminToken,
+    State(state): State<AppState>,
+    Query(query): Query<ListLinksQuery>,
+) -> Result<Json<ApiResponse<LinksPage>>, AppError> {
+    let result = ShortenerService::list(&state.db, query).await?;
+    Ok(Json(ApiResponse::success(result)))
+}
+
+pub async fn update_link(
+    _admin: AdminToken,
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    Json(payload): Json<PatchLinkRequest>,
+) -> Result<Json<ApiResponse<LinkResponse>>, AppError> {
+    let result = ShortenerService::update(&state.db, &state.origin, &code, payload).await?;
+    Ok(Json(ApiResponse::success(result)))
+}
+
+pub async fn delete_link(
+    _admin: AdminToken,
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<ApiResponse<LinkResponse>>, AppError> {
+    let result = ShortenerService::deactivate(&state.db, &state.origin, &code).await?;
+    Ok(Json(ApiResponse::success(result)))
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+use axum::{extract::Query, Json};
+use crate::{
+    models::{
+        requests::UtmBuilderQuery,
+        response::{ApiResponse, UtmBuilderResponse},
+    },
+    services::utm_service::UtmService,
+    errors::app_error::AppError,
+};
+
+pub async fn build_utm_url(
+    Query(query): Query<UtmBuilderQuery>,
+) -> Result<Json<ApiResponse<UtmBuilderResponse>>, AppError> {
+    let result = UtmService::build(query)?;
+    Ok(Json(ApiResponse::success(result)))
+}
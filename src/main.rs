@@ -1,42 +1,175 @@
-use std::env;
-use axum::{Router, routing::get, extract::State, Json};
-use sqlx::PgPool;
-use serde_json::json;
+use std::{env, net::{IpAddr, SocketAddr}, sync::Arc, time::Duration};
+use axum::{Router, routing::get, extract::State, http::StatusCode, Json};
+use app_state::{AppState, PublicOrigin};
+use models::response::{ApiResponse, HealthStatus};
+use middleware::rate_limit::RateLimiter;
 
+mod app_state;
 mod repositories;
 mod models;
 mod errors;
+mod extractors;
 mod db;
 mod services;
 mod handlers;
+mod middleware;
 mod routes;
+mod tasks;
+mod validation;
 
-async fn health_check(State(pool): State<PgPool>) -> Json<serde_json::Value> {
-    match sqlx::query("SELECT 1").execute(&pool).await {
-        Ok(_) => Json(json!({
-            "status": "healthy",
-            "database": "connected"
-        })),
-        Err(_) => Json(json!({
-            "status": "unhealthy",
-            "database": "disconnected"
-        }))
+const DEFAULT_BASE_URL: &str = "http://localhost:5000";
+
+/// Load-balancer probe. Answers 503 with a body (so not via AppError)
+/// when the database doesn't respond within two seconds.
+async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<ApiResponse<HealthStatus>>) {
+    let ping = sqlx::query("SELECT 1").execute(&state.db);
+
+    match tokio::time::timeout(Duration::from_secs(2), ping).await {
+        Ok(Ok(_)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(HealthStatus {
+                status: "healthy",
+                db: "ok",
+                pool_size: Some(state.db.size()),
+                idle_connections: Some(state.db.num_idle()),
+            })),
+        ),
+        _ => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ApiResponse {
+                success: false,
+                data: Some(HealthStatus {
+                    status: "unhealthy",
+                    db: "unreachable",
+                    pool_size: None,
+                    idle_connections: None,
+                }),
+                error: None,
+            }),
+        ),
+    }
+}
+
+/// Reads BASE_URL, the public origin short links are served from.
+/// Panics on anything that isn't an absolute http(s) URL.
+fn origin_from_env() -> PublicOrigin {
+    let raw = env::var("BASE_URL").unwrap_or_else(|_| {
+        tracing::warn!("BASE_URL is not set; defaulting to {}", DEFAULT_BASE_URL);
+        DEFAULT_BASE_URL.to_owned()
+    });
+
+    let parsed = url::Url::parse(&raw)
+        .unwrap_or_else(|e| panic!("BASE_URL {:?} is not a valid URL: {}", raw, e));
+    let host = match parsed.host_str() {
+        Some(host) if matches!(parsed.scheme(), "http" | "https") => host,
+        _ => panic!("BASE_URL {:?} must be an http or https URL with a host", raw),
+    };
+    // Even a bare `?` or `#` would end up in front of every short code.
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        panic!("BASE_URL {:?} must not have a query or fragment", raw);
+    }
+
+    PublicOrigin {
+        base_url: raw.trim_end_matches('/').to_owned(),
+        host: host.trim_end_matches('.').to_owned(),
+        path_segments: parsed
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        allow_chain_redirects: env::var("ALLOW_CHAIN_REDIRECTS").is_ok_and(|v| v == "true"),
     }
 }
 
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
+    tracing_subscriber::fmt::init();
 
     let database_url = env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set");
 
+    let rate_limit_rpm = env::var("RATE_LIMIT_RPM")
+        .map(|v| {
+            v.parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .expect("RATE_LIMIT_RPM must be a positive integer")
+        })
+        .unwrap_or(60);
+    let rate_limit_window_secs = env::var("RATE_LIMIT_WINDOW_SECS")
+        .map(|v| {
+            v.parse()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .expect("RATE_LIMIT_WINDOW_SECS must be a positive integer")
+        })
+        .unwrap_or(60);
+    let link_cleanup_interval_secs = env::var("LINK_CLEANUP_INTERVAL_SECS")
+        .map(|v| {
+            v.parse()
+                .ok()
+                .filter(|&secs| secs > 0)
+                .expect("LINK_CLEANUP_INTERVAL_SECS must be a positive integer")
+        })
+        .unwrap_or(3600);
+
+    let origin = origin_from_env();
+
+    // Without this, X-Forwarded-For is ignored and limits key on the peer.
+    let trusted_proxies: Vec<IpAddr> = env::var("TRUSTED_PROXIES")
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|ip| !ip.is_empty())
+                .map(|ip| {
+                    ip.parse()
+                        .unwrap_or_else(|_| panic!("TRUSTED_PROXIES entry {:?} is not an IP address", ip))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let preview_image = env::var("PREVIEW_IMAGE_URL").ok().filter(|v| !v.is_empty());
+    if let Some(raw) = &preview_image {
+        let parsed = url::Url::parse(raw)
+            .unwrap_or_else(|e| panic!("PREVIEW_IMAGE_URL {:?} is not a valid URL: {}", raw, e));
+        if !matches!(parsed.scheme(), "http" | "https") {
+            panic!("PREVIEW_IMAGE_URL {:?} must be an http or https URL", raw);
+        }
+    }
+
+    let admin_token = env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+    if admin_token.is_none() {
+        tracing::warn!("ADMIN_TOKEN is not set; link management endpoints will reject every request");
+    }
+
     let pool = db::connect_db(&database_url).await;
+    db::run_migrations(&pool).await;
+
+    tasks::link_cleanup::spawn(pool.clone(), Duration::from_secs(link_cleanup_interval_secs));
+
+    let state = AppState {
+        db: pool,
+        origin: Arc::new(origin),
+        admin_token: admin_token.map(Arc::from),
+        preview_image: preview_image.map(Arc::from),
+        trusted_proxies: Arc::from(trusted_proxies),
+        shorten_limiter: Arc::new(RateLimiter::new(
+            rate_limit_rpm,
+            Duration::from_secs(rate_limit_window_secs),
+        )),
+        // Tight limit so the availability check can't be used to enumerate codes.
+        check_limiter: Arc::new(RateLimiter::new(30, Duration::from_secs(60))),
+    };
 
     let app = Router::new()
         .route("/health", get(health_check))
-        .nest("/api", routes::router::router())
-        .with_state(pool);
+        .route("/{code}/preview", get(handlers::preview_handler::preview))
+        .nest("/api", routes::router::router(&state))
+        .with_state(state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:5000")
         .await
@@ -44,5 +177,7 @@ async fn main() {
 
     println!("🚀 Server running on http://0.0.0.0:5000");
 
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
\ No newline at end of file
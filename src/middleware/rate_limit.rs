@@ -0,0 +1,250 @@
+use std::{
+    collections::VecDeque,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use dashmap::DashMap;
+use crate::errors::app_error::AppError;
+
+/// Above this many tracked IPs, idle entries are swept, at most once per window.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// Sliding-window counter: each IP keeps the instants of its requests
+/// inside the current window.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    hits: DashMap<IpAddr, VecDeque<Instant>>,
+    last_sweep: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit: limit.max(1),
+            window,
+            hits: DashMap::new(),
+            last_sweep: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records a hit for `ip`, or returns how long until it may retry.
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_n(ip, 1)
+    }
+
+    /// Records `n` hits at once, e.g. one per URL in a bulk request. Weights
+    /// above the limit are charged as the limit, so they use up the window.
+    pub fn check_n(&self, ip: IpAddr, n: usize) -> Result<(), Duration> {
+        self.check_at(ip, n, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, n: usize, now: Instant) -> Result<(), Duration> {
+        if self.hits.len() > SWEEP_THRESHOLD {
+            self.sweep(now);
+        }
+
+        let mut hits = self.hits.entry(ip).or_default();
+        while hits.front().is_some_and(|t| now.duration_since(*t) >= self.window) {
+            hits.pop_front();
+        }
+
+        let n = n.min(self.limit);
+        let excess = (hits.len() + n).saturating_sub(self.limit);
+        if excess > 0 {
+            // Retry once enough of the oldest hits have aged out.
+            let freeing = hits[excess - 1];
+            return Err(self.window - now.duration_since(freeing));
+        }
+
+        hits.extend(std::iter::repeat_n(now, n));
+        Ok(())
+    }
+
+    /// Drops IPs with no hits left in the window. Skipped if another request
+    /// is already sweeping or the last sweep was less than a window ago.
+    fn sweep(&self, now: Instant) {
+        let Ok(mut last_sweep) = self.last_sweep.try_lock() else {
+            return;
+        };
+        if now.duration_since(*last_sweep) < self.window {
+            return;
+        }
+        *last_sweep = now;
+
+        self.hits.retain(|_, hits| {
+            hits.back().is_some_and(|t| now.duration_since(*t) < self.window)
+        });
+    }
+}
+
+/// Middleware state: the limiter for this route plus the proxies whose
+/// X-Forwarded-For we believe.
+#[derive(Clone)]
+pub struct RateLimitState {
+    pub limiter: Arc<RateLimiter>,
+    pub trusted_proxies: Arc<[IpAddr]>,
+}
+
+pub async fn rate_limit(
+    State(state): State<RateLimitState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let ip = client_ip(request.headers(), peer, &state.trusted_proxies);
+
+    state
+        .limiter
+        .check(ip)
+        .map_err(|wait| AppError::RateLimited(retry_after_secs(wait)))?;
+
+    Ok(next.run(request).await)
+}
+
+/// Whole seconds for Retry-After, rounded up so a client that waits exactly
+/// that long is never turned away again.
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    secs.max(1)
+}
+
+/// The socket peer, unless it is a trusted proxy. Then X-Forwarded-For is
+/// walked from the right (the hops our proxies appended) and the first
+/// untrusted address wins. Anything left of that is client-supplied and
+/// could be spoofed.
+pub fn client_ip(headers: &HeaderMap, peer: SocketAddr, trusted_proxies: &[IpAddr]) -> IpAddr {
+    let mut client = peer.ip();
+    if !trusted_proxies.contains(&client) {
+        return client;
+    }
+
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+
+    for hop in hops.into_iter().rev() {
+        let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !trusted_proxies.contains(&ip) {
+            break;
+        }
+    }
+
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 7));
+    const OTHER_IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(203, 0, 113, 8));
+
+    #[test]
+    fn allows_up_to_the_limit_per_ip() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(IP, 1, now), Ok(()));
+        assert_eq!(limiter.check_at(IP, 1, now), Ok(()));
+        assert!(limiter.check_at(IP, 1, now).is_err());
+        assert_eq!(limiter.check_at(OTHER_IP, 1, now), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_wait_until_the_oldest_hit_leaves_the_window() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let start = Instant::now();
+
+        limiter.check_at(IP, 1, start).unwrap();
+        let wait = limiter.check_at(IP, 1, start + Duration::from_millis(500)).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(59_500));
+        assert_eq!(retry_after_secs(wait), 60);
+    }
+
+    #[test]
+    fn frees_hits_once_the_window_passes() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let start = Instant::now();
+
+        limiter.check_at(IP, 1, start).unwrap();
+        assert!(limiter.check_at(IP, 1, start + Duration::from_secs(59)).is_err());
+        assert_eq!(limiter.check_at(IP, 1, start + Duration::from_secs(60)), Ok(()));
+    }
+
+    #[test]
+    fn weighted_checks_charge_every_hit() {
+        let limiter = RateLimiter::new(5, Duration::from_secs(60));
+        let start = Instant::now();
+
+        limiter.check_at(IP, 1, start).unwrap();
+        limiter.check_at(IP, 3, start + Duration::from_secs(10)).unwrap();
+        // 4 of 5 used: a batch of 3 must wait for the 10s hits to expire.
+        let wait = limiter.check_at(IP, 3, start + Duration::from_secs(20)).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(50));
+        assert_eq!(limiter.check_at(IP, 1, start + Duration::from_secs(20)), Ok(()));
+    }
+
+    #[test]
+    fn weights_above_the_limit_take_the_whole_window() {
+        let limiter = RateLimiter::new(5, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(limiter.check_at(IP, 100, start), Ok(()));
+        let wait = limiter.check_at(IP, 1, start + Duration::from_secs(1)).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(59));
+    }
+
+    #[test]
+    fn zero_limit_still_allows_one_hit() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(IP, 1, now), Ok(()));
+        assert!(limiter.check_at(IP, 1, now).is_err());
+    }
+
+    #[test]
+    fn rounds_retry_after_up() {
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
+        assert_eq!(retry_after_secs(Duration::from_millis(1)), 1);
+        assert_eq!(retry_after_secs(Duration::from_secs(3)), 3);
+        assert_eq!(retry_after_secs(Duration::from_nanos(3_000_000_001)), 4);
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn ignores_forwarded_for_from_untrusted_peers() {
+        let peer: SocketAddr = "198.51.100.1:4000".parse().unwrap();
+        assert_eq!(client_ip(&forwarded("1.2.3.4"), peer, &[]), peer.ip());
+    }
+
+    #[test]
+    fn takes_the_rightmost_untrusted_hop_from_trusted_peers() {
+        let peer: SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let trusted = ["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+
+        let spoofed = forwarded("6.6.6.6, 1.2.3.4, 10.0.0.2");
+        assert_eq!(client_ip(&spoofed, peer, &trusted), "1.2.3.4".parse::<IpAddr>().unwrap());
+        assert_eq!(client_ip(&HeaderMap::new(), peer, &trusted), peer.ip());
+        assert_eq!(client_ip(&forwarded("junk"), peer, &trusted), peer.ip());
+    }
+}
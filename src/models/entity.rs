@@ -1,6 +1,19 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
+#[derive(sqlx::FromRow)]
 pub struct ShortUrl {
     pub id: Uuid,
     pub original_url: String,
     pub short_code: String,
-}
\ No newline at end of file
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A link about to be inserted; the database computes `expires_at`.
+pub struct NewShortUrl {
+    pub id: Uuid,
+    pub original_url: String,
+    pub short_code: String,
+    pub expires_in_seconds: Option<u64>,
+}
@@ -1,3 +1,4 @@
 pub mod entity;
+pub mod pagination;
 pub mod requests;
 pub mod response;
@@ -0,0 +1,105 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct PaginatedResponse<T> {
+    pub items: Vec<T>,
+    #[serde(flatten)]
+    pub meta: PaginationMeta,
+}
+
+#[derive(Serialize)]
+pub struct PaginationMeta {
+    pub total: i64,
+    pub page: u64,
+    pub per_page: u64,
+    pub total_pages: u64,
+    pub has_next: bool,
+    pub has_prev: bool,
+    pub next_page: Option<u64>,
+    pub prev_page: Option<u64>,
+}
+
+#[allow(dead_code)]
+impl PaginationMeta {
+    /// `page` is 1-based. A page past the end still gets a `prev_page`
+    /// pointing at the last real page so clients can recover.
+    pub fn new(total: i64, page: u64, per_page: u64) -> Self {
+        let total = total.max(0);
+        let page = page.max(1);
+        let per_page = per_page.max(1);
+        let total_pages = (total as u64).div_ceil(per_page);
+
+        let has_next = page < total_pages;
+        let has_prev = page > 1;
+
+        Self {
+            total,
+            page,
+            per_page,
+            total_pages,
+            has_next,
+            has_prev,
+            next_page: has_next.then(|| page + 1),
+            prev_page: has_prev.then(|| (page - 1).min(total_pages.max(1))),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl<T> PaginatedResponse<T> {
+    pub fn new(items: Vec<T>, total: i64, page: u64, per_page: u64) -> Self {
+        Self {
+            items,
+            meta: PaginationMeta::new(total, page, per_page),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_result_has_no_pages() {
+        let meta = PaginationMeta::new(0, 1, 20);
+        assert_eq!(meta.total_pages, 0);
+        assert!(!meta.has_next && !meta.has_prev);
+        assert_eq!((meta.next_page, meta.prev_page), (None, None));
+    }
+
+    #[test]
+    fn exact_multiple_of_per_page_has_no_extra_page() {
+        let meta = PaginationMeta::new(40, 1, 20);
+        assert_eq!(meta.total_pages, 2);
+        assert_eq!(meta.next_page, Some(2));
+
+        let meta = PaginationMeta::new(40, 2, 20);
+        assert!(!meta.has_next);
+        assert_eq!((meta.next_page, meta.prev_page), (None, Some(1)));
+    }
+
+    #[test]
+    fn page_past_the_end_points_back_at_the_last_page() {
+        let meta = PaginationMeta::new(45, 9, 20);
+        assert_eq!(meta.total_pages, 3);
+        assert!(!meta.has_next);
+        assert_eq!((meta.next_page, meta.prev_page), (None, Some(3)));
+
+        let meta = PaginationMeta::new(0, 4, 20);
+        assert_eq!(meta.prev_page, Some(1));
+    }
+
+    #[test]
+    fn zero_page_and_per_page_are_raised_to_one() {
+        let meta = PaginationMeta::new(3, 0, 0);
+        assert_eq!((meta.page, meta.per_page, meta.total_pages), (1, 1, 3));
+        assert_eq!((meta.next_page, meta.prev_page), (Some(2), None));
+    }
+
+    #[test]
+    fn negative_total_is_treated_as_empty() {
+        let meta = PaginationMeta::new(-5, 1, 20);
+        assert_eq!((meta.total, meta.total_pages), (0, 0));
+        assert!(!meta.has_next && !meta.has_prev);
+    }
+}
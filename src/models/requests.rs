@@ -1,7 +1,126 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
 
 #[derive(Deserialize)]
 pub struct CreateShortUrlRequest {
+    #[serde(deserialize_with = "deserialize_url")]
     pub url: String,
+    #[serde(alias = "custom_code")]
     pub short_code: Option<String>,
-}
\ No newline at end of file
+    pub expires_in_seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateBulkRequest {
+    pub urls: Vec<CreateShortUrlRequest>,
+}
+
+/// Only the fields present are changed. `"expires_at": null` clears the
+/// expiry; leaving the field out keeps it. `"original_url": null` is the
+/// same as leaving it out.
+#[derive(Deserialize)]
+pub struct PatchLinkRequest {
+    #[serde(default, deserialize_with = "deserialize_optional_url")]
+    pub original_url: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct ListLinksQuery {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ShortCodeCheckQuery {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct UtmBuilderQuery {
+    pub url: String,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub utm_content: Option<String>,
+    pub utm_term: Option<String>,
+}
+
+
+/// Trims pasted whitespace and lowercases the scheme (`HTTP://` -> `http://`),
+/// leaving the rest of the URL untouched.
+fn deserialize_url<'de, D>(d: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(d).map(|raw| normalize_url(&raw))
+}
+
+/// Like `deserialize_url`, but `null` reads as "not provided".
+fn deserialize_optional_url<'de, D>(d: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(d).map(|raw| raw.as_deref().map(normalize_url))
+}
+
+fn normalize_url(raw: &str) -> String {
+    let trimmed = raw.trim();
+
+    match trimmed.split_once("://") {
+        Some((scheme, rest))
+            if scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) =>
+        {
+            format!("{}://{}", scheme.to_ascii_lowercase(), rest)
+        }
+        _ => trimmed.to_owned(),
+    }
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+fn deserialize_present<'de, D, T>(d: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(d).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url_of(json: &str) -> String {
+        serde_json::from_str::<CreateShortUrlRequest>(json).unwrap().url
+    }
+
+    #[test]
+    fn trims_surrounding_whitespace() {
+        assert_eq!(url_of(r#"{"url": "  https://example.com/a \n"}"#), "https://example.com/a");
+    }
+
+    #[test]
+    fn lowercases_only_the_scheme() {
+        assert_eq!(url_of(r#"{"url": "HTTP://Example.COM/Some/Path"}"#), "http://Example.COM/Some/Path");
+        assert_eq!(url_of(r#"{"url": "Https://a.io/?Q=1"}"#), "https://a.io/?Q=1");
+    }
+
+    #[test]
+    fn passes_scheme_less_input_through() {
+        assert_eq!(url_of(r#"{"url": " Example.com/Path "}"#), "Example.com/Path");
+        assert_eq!(url_of(r#"{"url": "/Path://x"}"#), "/Path://x");
+    }
+
+    #[test]
+    fn patch_normalizes_original_url_and_reads_null_as_absent() {
+        let patch: PatchLinkRequest =
+            serde_json::from_str(r#"{"original_url": " HTTPS://a.io/X"}"#).unwrap();
+        assert_eq!(patch.original_url.as_deref(), Some("https://a.io/X"));
+
+        let patch: PatchLinkRequest = serde_json::from_str(r#"{"original_url": null}"#).unwrap();
+        assert_eq!(patch.original_url, None);
+    }
+}
@@ -1,9 +1,76 @@
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 #[derive(Serialize)]
 pub struct CreateShortUrlResponse {
     pub url: String,
     pub short_code: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct LinkResponse {
+    pub url: String,
+    pub short_code: String,
+    pub original_url: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+}
+
+#[derive(Serialize)]
+pub struct LinkSummary {
+    pub short_code: String,
+    pub original_url: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+}
+
+#[derive(Serialize)]
+pub struct LinksPage {
+    pub items: Vec<LinkSummary>,
+    /// Pass back as `cursor` for the next page; `null` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LinkPreviewResponse {
+    pub short_url: String,
+    pub short_code: String,
+    pub original_url: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// One slot per requested URL, in request order.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum BulkCreateResponse {
+    Created(CreateShortUrlResponse),
+    Failed { error: ApiErrorResponse },
+}
+
+#[derive(Serialize)]
+pub struct ShortCodeAvailabilityResponse {
+    pub available: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UtmBuilderResponse {
+    pub original_url: String,
+    pub tagged_url: String,
+    pub short_url_preview: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HealthStatus {
+    pub status: &'static str,
+    pub db: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_connections: Option<usize>,
 }
 
 #[derive(Serialize)]
@@ -1,8 +1,9 @@
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use crate::{
     errors::app_error::AppError,
-    models::entity::ShortUrl,
+    models::entity::{NewShortUrl, ShortUrl},
 };
 
 pub struct ShortenerRepository;
@@ -10,50 +11,185 @@ pub struct ShortenerRepository;
 impl ShortenerRepository {
     pub async fn insert(
         pool: &PgPool,
-        entity: &ShortUrl,
-    ) -> Result<(), AppError> {
-        sqlx::query!(
+        entity: &NewShortUrl,
+    ) -> Result<ShortUrl, AppError> {
+        let link = sqlx::query_as!(
+            ShortUrl,
             r#"
-            INSERT INTO links (id, original_url, short_code)
-            VALUES ($1, $2, $3)
+            INSERT INTO links (id, original_url, short_code, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))
+            RETURNING id, original_url, short_code, expires_at, is_active, created_at
             "#,
             entity.id,
             entity.original_url,
-            entity.short_code
+            entity.short_code,
+            entity.expires_in_seconds.map(|secs| secs as f64)
         )
-            .execute(pool)
+            .fetch_one(pool)
+            .await
+            .map_err(map_insert_error)?;
+
+        Ok(link)
+    }
+
+    /// Inserts every row in one statement, skipping rows whose short code
+    /// is already taken. Returns the rows that were inserted.
+    pub async fn insert_many(
+        pool: &PgPool,
+        entities: &[&NewShortUrl],
+    ) -> Result<Vec<ShortUrl>, AppError> {
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let mut builder: QueryBuilder<Postgres> =
+            QueryBuilder::new("INSERT INTO links (id, original_url, short_code, expires_at) ");
+        builder.push_values(entities, |mut row, entity| {
+            row.push_bind(entity.id)
+                .push_bind(&entity.original_url)
+                .push_bind(&entity.short_code)
+                .push("NOW() + make_interval(secs => ")
+                .push_bind_unseparated(entity.expires_in_seconds.map(|secs| secs as f64))
+                .push_unseparated(")");
+        });
+        builder.push(
+            " ON CONFLICT DO NOTHING RETURNING id, original_url, short_code, expires_at, is_active, created_at",
+        );
+
+        let inserted = builder
+            .build_query_as::<ShortUrl>()
+            .fetch_all(&mut *tx)
             .await
             .map_err(AppError::from)?;
 
-        Ok(())
+        tx.commit().await?;
+
+        Ok(inserted)
     }
 
-    #[allow(dead_code)]
-    pub async fn update(
+    pub async fn find_by_short_code(
         pool: &PgPool,
-        id: Uuid,
-        original_url: &str,
         short_code: &str,
-    ) -> Result<(), AppError> {
-        let result = sqlx::query!(
+    ) -> Result<Option<ShortUrl>, AppError> {
+        let link = sqlx::query_as!(
+            ShortUrl,
             r#"
-            UPDATE links
-            SET original_url = $1,
-                short_code = $2
-            WHERE id = $3
+            SELECT id, original_url, short_code, expires_at, is_active, created_at
+            FROM links
+            WHERE short_code = $1
             "#,
-            original_url,
-            short_code,
-            id
+            short_code
+        )
+            .fetch_optional(pool)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(link)
+    }
+
+    /// Newest first, strictly after `after` when given. `(created_at, id)`
+    /// breaks ties between rows created in the same instant.
+    pub async fn list(
+        pool: &PgPool,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: i64,
+    ) -> Result<Vec<ShortUrl>, AppError> {
+        let (after_created_at, after_id) = after.unzip();
+
+        let links = sqlx::query_as!(
+            ShortUrl,
+            r#"
+            SELECT id, original_url, short_code, expires_at, is_active, created_at
+            FROM links
+            WHERE $1::timestamptz IS NULL OR (created_at, id) < ($1, $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
+            "#,
+            after_created_at,
+            after_id,
+            limit
+        )
+            .fetch_all(pool)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(links)
+    }
+
+    pub async fn delete_expired(pool: &PgPool) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM links
+            WHERE expires_at < NOW()
+            "#
         )
             .execute(pool)
             .await
             .map_err(AppError::from)?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+        Ok(result.rows_affected())
+    }
+
+    /// Applies only the provided fields. Returns `None` for unknown codes.
+    pub async fn patch(
+        pool: &PgPool,
+        short_code: &str,
+        original_url: Option<&str>,
+        expires_at: Option<Option<DateTime<Utc>>>,
+        is_active: Option<bool>,
+    ) -> Result<Option<ShortUrl>, AppError> {
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new("UPDATE links SET ");
+        let mut fields = builder.separated(", ");
+        if let Some(original_url) = original_url {
+            fields.push("original_url = ").push_bind_unseparated(original_url);
+        }
+        if let Some(expires_at) = expires_at {
+            fields.push("expires_at = ").push_bind_unseparated(expires_at);
+        }
+        if let Some(is_active) = is_active {
+            fields.push("is_active = ").push_bind_unseparated(is_active);
         }
+        builder
+            .push(" WHERE short_code = ")
+            .push_bind(short_code)
+            .push(" RETURNING id, original_url, short_code, expires_at, is_active, created_at");
+
+        let link = builder
+            .build_query_as::<ShortUrl>()
+            .fetch_optional(pool)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(link)
+    }
+
+    pub async fn deactivate(
+        pool: &PgPool,
+        short_code: &str,
+    ) -> Result<Option<ShortUrl>, AppError> {
+        let link = sqlx::query_as!(
+            ShortUrl,
+            r#"
+            UPDATE links
+            SET is_active = FALSE
+            WHERE short_code = $1
+            RETURNING id, original_url, short_code, expires_at, is_active, created_at
+            "#,
+            short_code
+        )
+            .fetch_optional(pool)
+            .await
+            .map_err(AppError::from)?;
+
+        Ok(link)
+    }
+}
 
-        Ok(())
+fn map_insert_error(err: sqlx::Error) -> AppError {
+    match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => AppError::Conflict,
+        _ => AppError::from(err),
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,41 @@
-use axum::{Router, routing::post};
-use sqlx::PgPool;
-use crate::handlers::shortener_handler::create_short_url;
+use std::sync::Arc;
+use axum::{Router, middleware, routing::{get, patch, post}};
+use crate::{
+    app_state::AppState,
+    handlers::{
+        shortener_handler::{
+            check_short_code, create_short_url, create_short_urls_bulk, delete_link, list_links,
+            update_link,
+        },
+        utm_handler::build_utm_url,
+    },
+    middleware::rate_limit::{rate_limit, RateLimitState, RateLimiter},
+};
+
+pub fn router(state: &AppState) -> Router<AppState> {
+    let limited = |limiter: &Arc<RateLimiter>| {
+        middleware::from_fn_with_state(
+            RateLimitState {
+                limiter: limiter.clone(),
+                trusted_proxies: state.trusted_proxies.clone(),
+            },
+            rate_limit,
+        )
+    };
 
-pub fn router() -> Router<PgPool> {
     Router::new()
-        .route("/shorten", post(create_short_url))
-}
\ No newline at end of file
+        .route(
+            "/shorten",
+            post(create_short_url)
+                .route_layer(limited(&state.shorten_limiter)),
+        )
+        .route("/shorten/bulk", post(create_short_urls_bulk))
+        .route(
+            "/shorten/check",
+            get(check_short_code)
+                .route_layer(limited(&state.check_limiter)),
+        )
+        .route("/links", get(list_links))
+        .route("/links/{code}", patch(update_link).delete(delete_link))
+        .route("/utm-builder", get(build_utm_url))
+}
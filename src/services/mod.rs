@@ -1,3 +1,4 @@
 // Service modules will be declared here
 
-pub mod shortener_service;
\ No newline at end of file
+pub mod shortener_service;
+pub mod utm_service;
\ No newline at end of file
@@ -1,42 +1,407 @@
+use std::{collections::{HashMap, HashSet}, ops::RangeInclusive};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 use nanoid::nanoid;
 use sqlx::PgPool;
+use url::Url;
 use crate::{
+    app_state::PublicOrigin,
     models::{
-        requests::CreateShortUrlRequest,
-        response::CreateShortUrlResponse,
-        entity::ShortUrl,
+        requests::{CreateBulkRequest, CreateShortUrlRequest, ListLinksQuery, PatchLinkRequest},
+        response::{
+            BulkCreateResponse, CreateShortUrlResponse, LinkResponse, LinkSummary, LinksPage,
+            ShortCodeAvailabilityResponse,
+        },
+        entity::{NewShortUrl, ShortUrl},
     },
     repositories::shortener_repo::ShortenerRepository,
     errors::app_error::AppError,
+    validation::{validate_short_code, validate_url},
 };
 
+const MAX_BULK_URLS: usize = 100;
+const DEFAULT_LIST_LIMIT: i64 = 20;
+const MAX_LIST_LIMIT: i64 = 100;
+/// One minute to one year.
+const EXPIRES_IN_SECONDS_RANGE: RangeInclusive<u64> = 60..=31_536_000;
+
 pub struct ShortenerService;
 
 impl ShortenerService {
     pub async fn create(
         pool: &PgPool,
+        origin: &PublicOrigin,
         payload: CreateShortUrlRequest,
     ) -> Result<CreateShortUrlResponse, AppError> {
-        if payload.url.is_empty() {
-            return Err(AppError::Validation);
+        let entity = Self::prepare(pool, origin, payload).await?;
+
+        let link = ShortenerRepository::insert(pool, &entity).await?;
+
+        Ok(Self::created(origin, link))
+    }
+
+    /// Every entry is validated on its own and reported in its own slot;
+    /// one bad or colliding entry never fails the rest of the batch.
+    pub async fn create_bulk(
+        pool: &PgPool,
+        origin: &PublicOrigin,
+        payload: CreateBulkRequest,
+    ) -> Result<Vec<BulkCreateResponse>, AppError> {
+        if payload.urls.is_empty() || payload.urls.len() > MAX_BULK_URLS {
+            return Err(AppError::Validation(format!(
+                "urls must contain between 1 and {} entries",
+                MAX_BULK_URLS
+            )));
+        }
+
+        let mut slots: Vec<Result<NewShortUrl, AppError>> = Vec::with_capacity(payload.urls.len());
+        let mut seen = HashSet::new();
+        for entry in payload.urls {
+            let slot = match Self::prepare(pool, origin, entry).await {
+                Ok(entity) if !seen.insert(entity.short_code.clone()) => Err(AppError::Conflict),
+                other => other,
+            };
+            slots.push(slot);
+        }
+
+        let valid: Vec<&NewShortUrl> = slots
+            .iter()
+            .filter_map(|slot| slot.as_ref().ok())
+            .collect();
+        let mut inserted: HashMap<String, ShortUrl> = ShortenerRepository::insert_many(pool, &valid)
+            .await?
+            .into_iter()
+            .map(|link| (link.short_code.clone(), link))
+            .collect();
+
+        Ok(slots
+            .into_iter()
+            .map(|slot| match slot.map(|entity| inserted.remove(&entity.short_code)) {
+                Ok(Some(link)) => BulkCreateResponse::Created(Self::created(origin, link)),
+                Ok(None) => BulkCreateResponse::Failed { error: AppError::Conflict.detail() },
+                Err(err) => BulkCreateResponse::Failed { error: err.detail() },
+            })
+            .collect())
+    }
+
+    pub async fn find(
+        pool: &PgPool,
+        short_code: &str,
+    ) -> Result<ShortUrl, AppError> {
+        let link = ShortenerRepository::find_by_short_code(pool, short_code)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if !link.is_active {
+            return Err(AppError::NotFound);
+        }
+
+        // Expired rows linger until the cleanup task's next pass.
+        if link.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Err(AppError::Gone);
+        }
+
+        Ok(link)
+    }
+
+    pub async fn update(
+        pool: &PgPool,
+        origin: &PublicOrigin,
+        short_code: &str,
+        payload: PatchLinkRequest,
+    ) -> Result<LinkResponse, AppError> {
+        if payload.original_url.is_none() && payload.expires_at.is_none() && payload.is_active.is_none() {
+            return Err(AppError::Validation("no fields to update".into()));
+        }
+
+        let original_url = match &payload.original_url {
+            Some(raw) => {
+                let url = validate_url(raw)?;
+                Self::reject_redirect_chain(pool, origin, &url, short_code).await?;
+                Some(url.to_string())
+            }
+            None => None,
+        };
+
+        // A past expiry would hand the link straight to the cleanup task,
+        // which deletes rather than deactivates.
+        if let Some(Some(expires_at)) = payload.expires_at
+            && expires_at <= Utc::now()
+        {
+            return Err(AppError::Validation("expires_at must be in the future".into()));
+        }
+
+        let link = ShortenerRepository::patch(
+            pool,
+            short_code,
+            original_url.as_deref(),
+            payload.expires_at,
+            payload.is_active,
+        )
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        Ok(Self::link_response(origin, link))
+    }
+
+    pub async fn deactivate(
+        pool: &PgPool,
+        origin: &PublicOrigin,
+        short_code: &str,
+    ) -> Result<LinkResponse, AppError> {
+        let link = ShortenerRepository::deactivate(pool, short_code)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        Ok(Self::link_response(origin, link))
+    }
+
+    pub async fn list(
+        pool: &PgPool,
+        query: ListLinksQuery,
+    ) -> Result<LinksPage, AppError> {
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+        if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "limit must be between 1 and {}",
+                MAX_LIST_LIMIT
+            )));
         }
-        
+
+        let after = query.cursor.as_deref().map(decode_cursor).transpose()?;
+
+        // One extra row tells us whether another page exists.
+        let mut links = ShortenerRepository::list(pool, after, limit + 1).await?;
+        let next_cursor = if links.len() as i64 > limit {
+            links.truncate(limit as usize);
+            links.last().map(|link| encode_cursor(link.created_at, link.id))
+        } else {
+            None
+        };
+
+        Ok(LinksPage {
+            items: links
+                .into_iter()
+                .map(|link| LinkSummary {
+                    short_code: link.short_code,
+                    original_url: link.original_url,
+                    created_at: link.created_at,
+                    expires_at: link.expires_at,
+                    is_active: link.is_active,
+                })
+                .collect(),
+            next_cursor,
+        })
+    }
+
+    async fn prepare(
+        pool: &PgPool,
+        origin: &PublicOrigin,
+        payload: CreateShortUrlRequest,
+    ) -> Result<NewShortUrl, AppError> {
+        let url = validate_url(&payload.url)?;
+
+        if let Some(secs) = payload.expires_in_seconds
+            && !EXPIRES_IN_SECONDS_RANGE.contains(&secs)
+        {
+            return Err(AppError::Validation(format!(
+                "expires_in_seconds must be between {} and {}",
+                EXPIRES_IN_SECONDS_RANGE.start(),
+                EXPIRES_IN_SECONDS_RANGE.end()
+            )));
+        }
+
+        if let Some(code) = &payload.short_code {
+            validate_short_code(code)
+                .map_err(|issue| AppError::Validation(issue.message()))?;
+        }
+
         let code = payload
             .short_code
             .unwrap_or_else(|| nanoid!(8));
-        
-        let entity = ShortUrl {
+
+        Self::reject_redirect_chain(pool, origin, &url, &code).await?;
+
+        Ok(NewShortUrl {
             id: Uuid::new_v4(),
-            original_url: payload.url.clone(),
-            short_code: code.clone(),
-        };
-        
-        ShortenerRepository::insert(pool, &entity).await?;
-        
-        Ok(CreateShortUrlResponse {
-            url: format!("http://localhost:3000/{}", code),
+            original_url: url.to_string(),
             short_code: code,
+            expires_in_seconds: payload.expires_in_seconds,
         })
     }
-}
\ No newline at end of file
+
+    fn created(origin: &PublicOrigin, link: ShortUrl) -> CreateShortUrlResponse {
+        CreateShortUrlResponse {
+            url: format!("{}/{}", origin.base_url, link.short_code),
+            short_code: link.short_code,
+            expires_at: link.expires_at,
+        }
+    }
+
+    fn link_response(origin: &PublicOrigin, link: ShortUrl) -> LinkResponse {
+        LinkResponse {
+            url: format!("{}/{}", origin.base_url, link.short_code),
+            short_code: link.short_code,
+            original_url: link.original_url,
+            expires_at: link.expires_at,
+            is_active: link.is_active,
+        }
+    }
+
+    pub async fn check_availability(
+        pool: &PgPool,
+        code: &str,
+    ) -> Result<ShortCodeAvailabilityResponse, AppError> {
+        let reason = match validate_short_code(code) {
+            Err(issue) => Some(issue.as_str()),
+            Ok(()) if ShortenerRepository::find_by_short_code(pool, code).await?.is_some() =>
+                Some("taken"),
+            Ok(()) => None,
+        };
+
+        Ok(ShortCodeAvailabilityResponse {
+            available: reason.is_none(),
+            reason: reason.map(str::to_owned),
+        })
+    }
+
+    /// Refuses destinations on our own origin whose short-code segment is a
+    /// short code (or the code being created), since following them would
+    /// bounce through this service again, unless chains are allowed.
+    async fn reject_redirect_chain(
+        pool: &PgPool,
+        origin: &PublicOrigin,
+        destination: &Url,
+        code: &str,
+    ) -> Result<(), AppError> {
+        if origin.allow_chain_redirects {
+            return Ok(());
+        }
+
+        let Some(target) = Self::chain_target(origin, destination) else {
+            return Ok(());
+        };
+
+        if target == code
+            || ShortenerRepository::find_by_short_code(pool, target).await?.is_some()
+        {
+            return Err(AppError::Validation("circular redirect detected".into()));
+        }
+
+        Ok(())
+    }
+
+    /// The segment a destination on our own origin would be looked up as:
+    /// the first one after BASE_URL's path. `None` for other hosts or paths.
+    fn chain_target<'a>(origin: &PublicOrigin, destination: &'a Url) -> Option<&'a str> {
+        // `example.com.` is the same host as `example.com`.
+        let host = destination.host_str()?.trim_end_matches('.');
+        if host != origin.host {
+            return None;
+        }
+
+        let mut segments = destination.path_segments()?;
+        for prefix in &origin.path_segments {
+            if segments.next()? != prefix {
+                return None;
+            }
+        }
+
+        segments.next().filter(|segment| !segment.is_empty())
+    }
+}
+
+/// Cursors are opaque to clients: base64 of `<created_at RFC 3339>|<id>`.
+fn encode_cursor(created_at: DateTime<Utc>, id: Uuid) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}|{}", created_at.to_rfc3339(), id))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, Uuid), AppError> {
+    let invalid = || AppError::Validation("cursor is invalid".into());
+
+    let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+    let (created_at, id) = raw.split_once('|').ok_or_else(invalid)?;
+
+    let created_at = DateTime::parse_from_rfc3339(created_at)
+        .map_err(|_| invalid())?
+        .with_timezone(&Utc);
+    let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+    Ok((created_at, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn origin(base_url: &str, path_segments: &[&str]) -> PublicOrigin {
+        PublicOrigin {
+            base_url: base_url.to_owned(),
+            host: Url::parse(base_url).unwrap().host_str().unwrap().to_owned(),
+            path_segments: path_segments.iter().map(|s| s.to_string()).collect(),
+            allow_chain_redirects: false,
+        }
+    }
+
+    fn chain_target(origin: &PublicOrigin, destination: &str) -> Option<String> {
+        let destination = Url::parse(destination).unwrap();
+        ShortenerService::chain_target(origin, &destination).map(str::to_owned)
+    }
+
+    #[test]
+    fn chain_target_is_the_first_segment_on_our_host() {
+        let origin = origin("https://sho.rt", &[]);
+        assert_eq!(chain_target(&origin, "https://sho.rt/abc"), Some("abc".into()));
+        assert_eq!(chain_target(&origin, "https://SHO.RT./abc/x"), Some("abc".into()));
+        assert_eq!(chain_target(&origin, "https://sho.rt/"), None);
+        assert_eq!(chain_target(&origin, "https://other.rt/abc"), None);
+    }
+
+    #[test]
+    fn chain_target_skips_the_base_url_path() {
+        let origin = origin("https://x.com/s", &["s"]);
+        assert_eq!(chain_target(&origin, "https://x.com/s/abc"), Some("abc".into()));
+        assert_eq!(chain_target(&origin, "https://x.com/s/"), None);
+        assert_eq!(chain_target(&origin, "https://x.com/s"), None);
+        assert_eq!(chain_target(&origin, "https://x.com/abc"), None);
+    }
+
+    fn rejects(cursor: &str) {
+        match decode_cursor(cursor) {
+            Err(AppError::Validation(message)) => assert_eq!(message, "cursor is invalid"),
+            other => panic!("expected invalid cursor for {cursor:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cursor_round_trips_with_microsecond_precision() {
+        // Postgres timestamptz keeps microseconds; the cursor must too.
+        let created_at = DateTime::parse_from_rfc3339("2026-10-14T09:30:15.123456Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let id = Uuid::new_v4();
+
+        assert_eq!(decode_cursor(&encode_cursor(created_at, id)).unwrap(), (created_at, id));
+    }
+
+    #[test]
+    fn cursor_rejects_bad_base64() {
+        rejects("not base64!");
+        rejects("=");
+    }
+
+    #[test]
+    fn cursor_rejects_missing_separator() {
+        rejects(&URL_SAFE_NO_PAD.encode("2026-10-14T09:30:15Z"));
+    }
+
+    #[test]
+    fn cursor_rejects_bad_uuid_or_timestamp() {
+        let id = Uuid::new_v4();
+        rejects(&URL_SAFE_NO_PAD.encode("2026-10-14T09:30:15Z|not-a-uuid"));
+        rejects(&URL_SAFE_NO_PAD.encode(format!("yesterday|{id}")));
+        rejects(&URL_SAFE_NO_PAD.encode(format!("2026-10-14 09:30|{id}")));
+    }
+}
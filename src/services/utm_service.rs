@@ -0,0 +1,61 @@
+use url::Url;
+use crate::{
+    models::{
+        requests::UtmBuilderQuery,
+        response::UtmBuilderResponse,
+    },
+    errors::app_error::AppError,
+};
+
+pub struct UtmService;
+
+impl UtmService {
+    pub fn build(query: UtmBuilderQuery) -> Result<UtmBuilderResponse, AppError> {
+        let mut url = Url::parse(query.url.trim())
+            .map_err(|_| AppError::Validation("url is not a valid URL".into()))?;
+
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::Validation("url must use http or https".into()));
+        }
+
+        let params: Vec<(&str, &str)> = [
+            ("utm_source", &query.utm_source),
+            ("utm_medium", &query.utm_medium),
+            ("utm_campaign", &query.utm_campaign),
+            ("utm_content", &query.utm_content),
+            ("utm_term", &query.utm_term),
+        ]
+            .into_iter()
+            .filter_map(|(key, value)| {
+                value
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| (key, v))
+            })
+            .collect();
+
+        if params.is_empty() {
+            return Err(AppError::Validation("at least one utm parameter is required".into()));
+        }
+
+        // Keep the destination's own query string, but let the new values
+        // replace any UTM params it already carries instead of duplicating them.
+        let existing: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| !params.iter().any(|(name, _)| name == key))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(existing)
+            .extend_pairs(params);
+
+        Ok(UtmBuilderResponse {
+            original_url: query.url,
+            tagged_url: url.to_string(),
+            short_url_preview: None,
+        })
+    }
+}
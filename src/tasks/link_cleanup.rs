@@ -0,0 +1,18 @@
+use std::time::Duration;
+use sqlx::PgPool;
+use crate::repositories::shortener_repo::ShortenerRepository;
+
+/// Hard-deletes expired links every `every`, for as long as the server runs.
+pub fn spawn(pool: PgPool, every: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            match ShortenerRepository::delete_expired(&pool).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!(deleted, "removed expired links"),
+                Err(err) => tracing::warn!(error = %err, "expired link cleanup failed"),
+            }
+        }
+    });
+}
@@ -0,0 +1,227 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+use crate::errors::app_error::AppError;
+
+pub const SHORT_CODE_MIN_LEN: usize = 3;
+pub const SHORT_CODE_MAX_LEN: usize = 32;
+
+/// Codes that would shadow our own routes.
+const RESERVED_SHORT_CODES: &[&str] = &["api", "health", "preview", "admin", "static"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ShortCodeIssue {
+    TooShort,
+    TooLong,
+    InvalidCharacters,
+    Reserved,
+}
+
+impl ShortCodeIssue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShortCodeIssue::TooShort => "too_short",
+            ShortCodeIssue::TooLong => "too_long",
+            ShortCodeIssue::InvalidCharacters => "invalid_characters",
+            ShortCodeIssue::Reserved => "reserved",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ShortCodeIssue::TooShort =>
+                format!("short_code must be at least {} characters", SHORT_CODE_MIN_LEN),
+            ShortCodeIssue::TooLong =>
+                format!("short_code must be at most {} characters", SHORT_CODE_MAX_LEN),
+            ShortCodeIssue::InvalidCharacters =>
+                "short_code may only contain letters, digits, '-' and '_'".into(),
+            ShortCodeIssue::Reserved =>
+                "short_code is reserved".into(),
+        }
+    }
+}
+
+/// Custom codes are limited to the alphabet nanoid uses for generated ones.
+pub fn validate_short_code(code: &str) -> Result<(), ShortCodeIssue> {
+    if !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(ShortCodeIssue::InvalidCharacters);
+    }
+    if code.len() < SHORT_CODE_MIN_LEN {
+        return Err(ShortCodeIssue::TooShort);
+    }
+    if code.len() > SHORT_CODE_MAX_LEN {
+        return Err(ShortCodeIssue::TooLong);
+    }
+    if RESERVED_SHORT_CODES.iter().any(|r| r.eq_ignore_ascii_case(code)) {
+        return Err(ShortCodeIssue::Reserved);
+    }
+    Ok(())
+}
+
+/// Parses a destination URL, allowing only public http(s) targets so a
+/// short link can't be pointed at localhost or internal networks.
+pub fn validate_url(raw: &str) -> Result<Url, AppError> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(AppError::Validation("url is required".into()));
+    }
+
+    let url = Url::parse(raw)
+        .map_err(|e| AppError::Validation(format!("url is not a valid URL: {}", e)))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(AppError::Validation("url must use http or https".into()));
+    }
+
+    match url.host() {
+        None => return Err(AppError::Validation("url must have a host".into())),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.');
+            if domain.eq_ignore_ascii_case("localhost")
+                || domain.to_ascii_lowercase().ends_with(".localhost")
+            {
+                return Err(AppError::Validation("url must not point to localhost".into()));
+            }
+        }
+        Some(Host::Ipv4(ip)) if is_internal_ipv4(ip) => {
+            return Err(AppError::Validation(
+                "url must not point to a private or loopback address".into(),
+            ));
+        }
+        Some(Host::Ipv6(ip)) if is_internal_ipv6(ip) => {
+            return Err(AppError::Validation(
+                "url must not point to a private or loopback address".into(),
+            ));
+        }
+        Some(_) => {}
+    }
+
+    Ok(url)
+}
+
+/// RFC 1918 and loopback, plus link-local (cloud metadata) and 0.0.0.0.
+fn is_internal_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified()
+}
+
+fn is_internal_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_internal_ipv4(mapped);
+    }
+    ip.is_loopback() || ip.is_unspecified() || ip.is_unique_local() || ip.is_unicast_link_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(raw: &str) -> String {
+        match validate_url(raw) {
+            Err(AppError::Validation(message)) => message,
+            other => panic!("expected validation error for {raw:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_public_http_and_https() {
+        assert_eq!(validate_url("https://example.com").unwrap().as_str(), "https://example.com/");
+        assert!(validate_url("http://a.io").is_ok());
+        assert!(validate_url("https://8.8.8.8/dns").is_ok());
+    }
+
+    #[test]
+    fn canonicalizes_the_url() {
+        let url = validate_url("  HTTPS://Example.COM:443/a/../b  ").unwrap();
+        assert_eq!(url.to_string(), "https://example.com/b");
+    }
+
+    #[test]
+    fn rejects_empty() {
+        assert_eq!(rejection(""), "url is required");
+        assert_eq!(rejection("   "), "url is required");
+    }
+
+    #[test]
+    fn rejects_unparseable() {
+        assert!(rejection("not a url").starts_with("url is not a valid URL"));
+        assert!(rejection("http://").starts_with("url is not a valid URL"));
+    }
+
+    #[test]
+    fn rejects_non_http_schemes() {
+        for raw in ["ftp://example.com", "javascript:alert(1)", "file:///etc/passwd", "data:text/html,hi"] {
+            assert_eq!(rejection(raw), "url must use http or https", "{raw}");
+        }
+    }
+
+    #[test]
+    fn rejects_localhost() {
+        for raw in ["http://localhost", "http://LOCALHOST:8080/x", "http://api.localhost", "http://localhost./"] {
+            assert_eq!(rejection(raw), "url must not point to localhost", "{raw}");
+        }
+    }
+
+    #[test]
+    fn rejects_loopback_addresses() {
+        for raw in ["http://127.0.0.1", "http://127.1.2.3:9000", "http://2130706433", "http://[::1]/"] {
+            assert_eq!(rejection(raw), "url must not point to a private or loopback address", "{raw}");
+        }
+    }
+
+    #[test]
+    fn rejects_private_ranges() {
+        for raw in [
+            "http://10.0.0.1",
+            "http://172.16.0.1",
+            "http://172.31.255.255",
+            "http://192.168.1.1",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0",
+            "http://[fd00::1]",
+            "http://[fe80::1]",
+            "http://[::ffff:10.0.0.1]",
+        ] {
+            assert_eq!(rejection(raw), "url must not point to a private or loopback address", "{raw}");
+        }
+    }
+
+    #[test]
+    fn allows_public_neighbours_of_private_ranges() {
+        assert!(validate_url("http://172.32.0.1").is_ok());
+        assert!(validate_url("http://192.169.0.1").is_ok());
+        assert!(validate_url("http://11.0.0.1").is_ok());
+    }
+
+    #[test]
+    fn accepts_short_codes_in_the_nanoid_alphabet() {
+        assert_eq!(validate_short_code("abc"), Ok(()));
+        assert_eq!(validate_short_code("My-Link_2"), Ok(()));
+        assert_eq!(validate_short_code(&"a".repeat(SHORT_CODE_MAX_LEN)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_short_codes_by_length() {
+        assert_eq!(validate_short_code(""), Err(ShortCodeIssue::TooShort));
+        assert_eq!(validate_short_code("ab"), Err(ShortCodeIssue::TooShort));
+        assert_eq!(
+            validate_short_code(&"a".repeat(SHORT_CODE_MAX_LEN + 1)),
+            Err(ShortCodeIssue::TooLong),
+        );
+    }
+
+    #[test]
+    fn rejects_short_codes_with_other_characters() {
+        assert_eq!(validate_short_code("has space"), Err(ShortCodeIssue::InvalidCharacters));
+        assert_eq!(validate_short_code("a/b/c"), Err(ShortCodeIssue::InvalidCharacters));
+        assert_eq!(validate_short_code("café"), Err(ShortCodeIssue::InvalidCharacters));
+        // Characters are checked before length.
+        assert_eq!(validate_short_code("a."), Err(ShortCodeIssue::InvalidCharacters));
+    }
+
+    #[test]
+    fn rejects_reserved_short_codes_in_any_case() {
+        assert_eq!(validate_short_code("api"), Err(ShortCodeIssue::Reserved));
+        assert_eq!(validate_short_code("Health"), Err(ShortCodeIssue::Reserved));
+        assert_eq!(validate_short_code("PREVIEW"), Err(ShortCodeIssue::Reserved));
+        assert_eq!(validate_short_code("apis"), Ok(()));
+    }
+}